@@ -9,13 +9,7 @@ fn criterion_benchmark(c: &mut Criterion) {
     let recipe: Recipe = serde_json::from_reader(recipe_reader).unwrap();
     let recipe = recipe.complement();
     for super_word in recipe.super_words.clone() {
-        let mut word_generator = WordGenerator {
-            super_languages: &recipe.super_languages,
-            super_word,
-            words: Vec::new(),
-            limit: 10000000,
-            weight_sum: 0.0,
-        };
+        let mut word_generator = WordGenerator::new(&recipe.super_languages, super_word, 10000000);
         c.bench_function(
             &format!("generate {}", &word_generator.super_word.meaning),
             |b| b.iter(|| word_generator.generate()),
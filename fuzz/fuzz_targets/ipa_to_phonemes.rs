@@ -0,0 +1,8 @@
+#![no_main]
+use bacitit_word_generator::convert;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|ipa: &str| {
+    convert::ipa_to_phonemes(ipa);
+    let _ = convert::try_ipa_to_phonemes(ipa);
+});
@@ -0,0 +1,9 @@
+#![no_main]
+use bacitit_word_generator::convert;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|ipa: &str| {
+    let phonemes = convert::ipa_to_phonemes(ipa);
+    let loan = convert::phonemes_to_loan(&phonemes);
+    assert_eq!(convert::loan_to_phonemes(&loan), phonemes);
+});
@@ -1,6 +1,8 @@
-use std::fs;
+use std::{collections::HashSet, error, fmt, fs};
 
-use crate::phoneme::Phoneme;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::phoneme::{Phoneme, PhonemeExt, Script};
 
 fn language_to_filename(language: &str) -> Option<String> {
     match language {
@@ -21,6 +23,10 @@ fn language_to_filename(language: &str) -> Option<String> {
     }
 }
 
+pub fn has_ipa_source(lang: &str) -> bool {
+    language_to_filename(lang).is_some()
+}
+
 pub fn to_ipa(word: &str, lang: &str) -> Option<String> {
     let dir = "./library/wikipron/data/scrape/tsv/";
     let filename = dir.to_string() + &language_to_filename(lang).unwrap();
@@ -137,6 +143,17 @@ fn ipa_to_phoneme(c: &char) -> Option<Phoneme> {
     }
 }
 
+// Multi-codepoint IPA symbols (after NFD) whose meaning differs from their
+// base character are matched as a prefix, so further diacritics are allowed.
+// Other clusters are matched by their base character only.
+fn ipa_cluster_to_phoneme(cluster: &str) -> Option<Phoneme> {
+    if cluster.starts_with("c\u{327}") {
+        Some(Phoneme::X)
+    } else {
+        cluster.chars().next().and_then(|c| ipa_to_phoneme(&c))
+    }
+}
+
 pub fn ipa_to_phonemes(word: &str) -> Vec<Phoneme> {
     use unicode_normalization::UnicodeNormalization;
     let word = word
@@ -149,51 +166,116 @@ pub fn ipa_to_phonemes(word: &str) -> Vec<Phoneme> {
         .replace("ʨ", "tɕ")
         .replace("ʥ", "dʑ");
     let mut alphabets = vec![];
-    for c in word.chars() {
-        match ipa_to_phoneme(&c) {
-            Some(x) => alphabets.push(x),
-            None => {}
+    for cluster in word.graphemes(true) {
+        if let Some(x) = ipa_cluster_to_phoneme(cluster) {
+            alphabets.push(x)
         }
     }
     alphabets
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConvertError {
+    pub word: String,
+    pub unrecognized: Vec<(usize, String)>,
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbols = self
+            .unrecognized
+            .iter()
+            .map(|(position, symbol)| format!("'{}' at byte {}", symbol, position))
+            .collect::<Vec<String>>()
+            .join(", ");
+        write!(
+            f,
+            "unrecognized IPA symbols in \"{}\": {}",
+            self.word, symbols
+        )
+    }
+}
+
+impl error::Error for ConvertError {}
+
+// Symbols which carry no segment of their own and are dropped silently.
+fn is_ipa_marker(c: char) -> bool {
+    use unicode_normalization::char::is_combining_mark;
+    is_combining_mark(c)
+        || matches!(
+            c,
+            'ˈ' | 'ˌ'
+                | '.'
+                | 'ː'
+                | 'ˑ'
+                | ' '
+                | '('
+                | ')'
+                | '‿'
+                | '|'
+                | '‖'
+                | 'ʰ'
+                | 'ʱ'
+                | 'ʲ'
+                | 'ʷ'
+                | 'ˠ'
+                | 'ˤ'
+                | 'ⁿ'
+                | 'ˡ'
+                | 'ᵝ'
+                | 'ʼ'
+                | '¹'
+                | '²'
+                | '³'
+                | '⁴'
+                | '⁵'
+                | '˥'
+                | '˦'
+                | '˧'
+                | '˨'
+                | '˩'
+        )
+}
+
+pub fn try_ipa_to_phonemes(word: &str) -> Result<Vec<Phoneme>, ConvertError> {
+    let unrecognized: Vec<(usize, String)> = word
+        .grapheme_indices(true)
+        .filter(|(_, cluster)| {
+            ipa_to_phonemes(cluster).is_empty() && !cluster.chars().all(is_ipa_marker)
+        })
+        .map(|(position, cluster)| (position, cluster.to_string()))
+        .collect();
+    if unrecognized.is_empty() {
+        Ok(ipa_to_phonemes(word))
+    } else {
+        Err(ConvertError {
+            word: word.to_string(),
+            unrecognized,
+        })
+    }
+}
+
 pub fn phoneme_to_string(phoneme: &Phoneme) -> String {
-    match phoneme {
-        Phoneme::P => "p".into(),
-        Phoneme::B => "b".into(),
-        Phoneme::T => "t".into(),
-        Phoneme::D => "d".into(),
-        Phoneme::K => "k".into(),
-        Phoneme::G => "g".into(),
-        Phoneme::M => "m".into(),
-        Phoneme::N => "n".into(),
-        Phoneme::R => "r".into(),
-        Phoneme::F => "f".into(),
-        Phoneme::V => "v".into(),
-        Phoneme::S => "s".into(),
-        Phoneme::Z => "z".into(),
-        Phoneme::C => "c".into(),
-        Phoneme::J => "j".into(),
-        Phoneme::X => "x".into(),
-        Phoneme::H => "h".into(),
-        Phoneme::Y => "y".into(),
-        Phoneme::L => "l".into(),
-        Phoneme::W => "w".into(),
-        Phoneme::I => "i".into(),
-        Phoneme::U => "u".into(),
-        Phoneme::E => "e".into(),
-        Phoneme::SCHWA => "-".into(),
-        Phoneme::O => "o".into(),
-        Phoneme::A => "a".into(),
-    }
-}
-
-pub fn phonemes_to_loan(phonemes: &Vec<Phoneme>) -> String {
+    phoneme.to_latin().into()
+}
+
+pub fn phonemes_to_loan(phonemes: &[Phoneme]) -> String {
     phonemes
         .iter()
-        .map(|p| phoneme_to_string(p))
-        .collect::<Vec<String>>()
+        .map(|p| p.to_latin())
+        .collect::<Vec<&str>>()
+        .join("")
+}
+
+pub fn phonemes_to_ipa(phonemes: &[Phoneme]) -> String {
+    phonemes.iter().map(|p| p.to_ipa()).collect()
+}
+
+pub fn phonemes_to_script(phonemes: &[Phoneme], script: Script) -> String {
+    phonemes
+        .iter()
+        .map(|p| p.render(script))
+        .collect::<Vec<&str>>()
         .join("")
 }
 
@@ -214,7 +296,7 @@ pub fn loan_to_phoneme(c: &char) -> Option<Phoneme> {
         's' => Some(Phoneme::S),
         'z' => Some(Phoneme::Z),
         'c' => Some(Phoneme::C),
-        'j' => Some(Phoneme::Y),
+        'j' => Some(Phoneme::J),
         'x' => Some(Phoneme::X),
         'h' => Some(Phoneme::H),
         'y' => Some(Phoneme::Y),
@@ -230,6 +312,30 @@ pub fn loan_to_phoneme(c: &char) -> Option<Phoneme> {
     }
 }
 
+pub fn inventory_to_phonemes(list: &str) -> Result<HashSet<Phoneme>, Vec<String>> {
+    let mut phonemes = HashSet::new();
+    let mut unknown = Vec::new();
+    for entry in list.split(&[',', '\n'][..]) {
+        let entry = entry.trim();
+        let mut chars = entry.chars();
+        match (chars.next(), chars.next()) {
+            (None, _) => {}
+            (Some(c), None) => match loan_to_phoneme(&c) {
+                Some(phoneme) => {
+                    phonemes.insert(phoneme);
+                }
+                None => unknown.push(entry.to_string()),
+            },
+            _ => unknown.push(entry.to_string()),
+        }
+    }
+    if unknown.is_empty() {
+        Ok(phonemes)
+    } else {
+        Err(unknown)
+    }
+}
+
 pub fn loan_to_phonemes(word: &str) -> Vec<Phoneme> {
     let mut alphabets = vec![];
     for c in word.chars() {
@@ -240,3 +346,123 @@ pub fn loan_to_phonemes(word: &str) -> Vec<Phoneme> {
     }
     alphabets
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combining_diacritics() {
+        assert_eq!(ipa_to_phonemes("ẽ"), vec![Phoneme::E]);
+        assert_eq!(
+            ipa_to_phonemes("lɑ̃ɡ"),
+            vec![Phoneme::L, Phoneme::A, Phoneme::G]
+        );
+        assert_eq!(ipa_to_phonemes("t̪"), vec![Phoneme::T]);
+        assert_eq!(ipa_to_phonemes("ṭ"), vec![Phoneme::T]);
+        assert_eq!(ipa_to_phonemes("pʰ"), vec![Phoneme::P]);
+        assert_eq!(ipa_to_phonemes("kʰa"), vec![Phoneme::K, Phoneme::A]);
+        assert_eq!(ipa_to_phonemes("bʱ"), vec![Phoneme::B]);
+    }
+
+    #[test]
+    fn test_phonemes_to_ipa() {
+        let consonants = vec![
+            Phoneme::P,
+            Phoneme::B,
+            Phoneme::T,
+            Phoneme::D,
+            Phoneme::K,
+            Phoneme::G,
+            Phoneme::M,
+            Phoneme::N,
+            Phoneme::R,
+            Phoneme::F,
+            Phoneme::V,
+            Phoneme::S,
+            Phoneme::Z,
+            Phoneme::C,
+            Phoneme::J,
+            Phoneme::X,
+            Phoneme::H,
+            Phoneme::Y,
+            Phoneme::L,
+            Phoneme::W,
+        ];
+        let vowels = vec![
+            Phoneme::I,
+            Phoneme::U,
+            Phoneme::E,
+            Phoneme::O,
+            Phoneme::A,
+            Phoneme::SCHWA,
+        ];
+        assert_eq!(phonemes_to_ipa(&consonants), "pbtdkɡmnrfvszʃʒxhjlw");
+        assert_eq!(phonemes_to_ipa(&vowels), "iueoaə");
+        assert_eq!(ipa_to_phonemes(&phonemes_to_ipa(&consonants)), consonants);
+        assert_eq!(ipa_to_phonemes(&phonemes_to_ipa(&vowels)), vowels);
+        assert_eq!(phonemes_to_ipa(&loan_to_phonemes("yizik")), "jizik");
+    }
+
+    #[test]
+    fn test_try_ipa_to_phonemes() {
+        assert_eq!(
+            try_ipa_to_phonemes("ˈlæŋ.ɡwɪd͡ʒ"),
+            Ok(ipa_to_phonemes("ˈlæŋ.ɡwɪd͡ʒ"))
+        );
+        assert_eq!(
+            try_ipa_to_phonemes("ʂɨ˧˥.tʰa"),
+            Ok(vec![Phoneme::C, Phoneme::I, Phoneme::T, Phoneme::A])
+        );
+        let error = try_ipa_to_phonemes("kœʔt").unwrap_err();
+        assert_eq!(
+            error.unrecognized,
+            vec![(1, "œ".to_string()), (3, "ʔ".to_string())]
+        );
+        assert_eq!(
+            error.to_string(),
+            "unrecognized IPA symbols in \"kœʔt\": 'œ' at byte 1, 'ʔ' at byte 3"
+        );
+    }
+
+    #[test]
+    fn test_phonemes_to_script() {
+        let phonemes = loan_to_phonemes("yizik");
+        assert_eq!(phonemes_to_script(&phonemes, Script::Latin), "yizik");
+        assert_eq!(phonemes_to_script(&phonemes, Script::Cyrillic), "йизик");
+        assert_eq!(
+            phonemes_to_script(&loan_to_phonemes("cprax-"), Script::Cyrillic),
+            "шпрахъ"
+        );
+    }
+
+    #[test]
+    fn test_loan_round_trip() {
+        let loan = "pbtdkgmnrfvszcjxhylwiueoa-";
+        assert_eq!(phonemes_to_loan(&loan_to_phonemes(loan)), loan);
+    }
+
+    #[test]
+    fn test_inventory_to_phonemes() {
+        let inventory = inventory_to_phonemes("p, t\nk,\n m\n").unwrap();
+        assert_eq!(
+            inventory,
+            vec![Phoneme::P, Phoneme::T, Phoneme::K, Phoneme::M]
+                .into_iter()
+                .collect()
+        );
+        assert_eq!(
+            inventory_to_phonemes("p, q, th\nt"),
+            Err(vec!["q".to_string(), "th".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_multi_codepoint_symbols() {
+        assert_eq!(ipa_to_phonemes("ç"), vec![Phoneme::X]);
+        assert_eq!(ipa_to_phonemes("ˈɪç"), vec![Phoneme::I, Phoneme::X]);
+        assert_eq!(ipa_to_phonemes("ç\u{32c}"), vec![Phoneme::X]);
+        assert_eq!(ipa_to_phonemes("ä"), vec![Phoneme::A]);
+        assert_eq!(ipa_to_phonemes("t͡ʃ"), vec![Phoneme::T, Phoneme::C]);
+    }
+}
@@ -20,28 +20,57 @@ pub enum Language {
     Indonesian,
     Urdu,
     German,
+    Bengali,
+    Marathi,
+    Telugu,
+    Turkish,
+    Tamil,
+    Vietnamese,
+    Korean,
+    Italian,
+    Punjabi,
+    Javanese,
+    Persian,
+    Gujarati,
+    /// Any other ISO 639-1 code. Weights are still looked up by this code.
+    Other(String),
+}
+
+impl Language {
+    pub fn code(&self) -> &str {
+        match self {
+            Language::Chinese => "zh",
+            Language::English => "en",
+            Language::Spanish => "es",
+            Language::Hindi => "hi",
+            Language::Portuguese => "pt",
+            Language::Russian => "ru",
+            Language::French => "fr",
+            Language::Arabic => "ar",
+            Language::Japanese => "ja",
+            Language::Indonesian => "id",
+            Language::Urdu => "ur",
+            Language::German => "de",
+            Language::Bengali => "bn",
+            Language::Marathi => "mr",
+            Language::Telugu => "te",
+            Language::Turkish => "tr",
+            Language::Tamil => "ta",
+            Language::Vietnamese => "vi",
+            Language::Korean => "ko",
+            Language::Italian => "it",
+            Language::Punjabi => "pa",
+            Language::Javanese => "jv",
+            Language::Persian => "fa",
+            Language::Gujarati => "gu",
+            Language::Other(code) => code.as_str(),
+        }
+    }
 }
 
 impl fmt::Display for Language {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Language::Chinese => "zh",
-                Language::English => "en",
-                Language::Spanish => "es",
-                Language::Hindi => "hi",
-                Language::Portuguese => "pt",
-                Language::Russian => "ru",
-                Language::French => "fr",
-                Language::Arabic => "ar",
-                Language::Japanese => "ja",
-                Language::Indonesian => "id",
-                Language::Urdu => "ur",
-                Language::German => "de",
-            }
-        )
+        write!(f, "{}", self.code())
     }
 }
 
@@ -64,7 +93,20 @@ impl StringExt for String {
             "id" => Some(Language::Indonesian),
             "ur" => Some(Language::Urdu),
             "de" => Some(Language::German),
-            _ => None,
+            "bn" => Some(Language::Bengali),
+            "mr" => Some(Language::Marathi),
+            "te" => Some(Language::Telugu),
+            "tr" => Some(Language::Turkish),
+            "ta" => Some(Language::Tamil),
+            "vi" => Some(Language::Vietnamese),
+            "ko" => Some(Language::Korean),
+            "it" => Some(Language::Italian),
+            "pa" => Some(Language::Punjabi),
+            "jv" => Some(Language::Javanese),
+            "fa" => Some(Language::Persian),
+            "gu" => Some(Language::Gujarati),
+            "" => None,
+            code => Some(Language::Other(code.to_string())),
         }
     }
 }
@@ -119,6 +161,100 @@ mod tests {
         assert_eq!("zh".to_string().iso_639().unwrap(), Language::Chinese);
     }
 
+    #[test]
+    fn test_code() {
+        let languages = vec![
+            Language::Chinese,
+            Language::English,
+            Language::Spanish,
+            Language::Hindi,
+            Language::Portuguese,
+            Language::Russian,
+            Language::French,
+            Language::Arabic,
+            Language::Japanese,
+            Language::Indonesian,
+            Language::Urdu,
+            Language::German,
+            Language::Bengali,
+            Language::Marathi,
+            Language::Telugu,
+            Language::Turkish,
+            Language::Tamil,
+            Language::Vietnamese,
+            Language::Korean,
+            Language::Italian,
+            Language::Punjabi,
+            Language::Javanese,
+            Language::Persian,
+            Language::Gujarati,
+            Language::Other("sw".to_string()),
+        ];
+        for language in languages {
+            assert_eq!(language.code(), language.to_string());
+            assert_eq!(
+                language.code().to_string().iso_639(),
+                Some(language.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn test_other_language() {
+        let other = "sw".to_string().iso_639().unwrap();
+        assert_eq!(other, Language::Other("sw".to_string()));
+        assert_eq!(other.to_string(), "sw");
+        let language: Language = serde_json::from_str(r#""sw""#).unwrap();
+        assert_eq!(language, other);
+        assert_eq!(serde_json::to_string(&language).unwrap(), r#""sw""#);
+        assert_eq!("".to_string().iso_639(), None);
+        assert!(serde_json::from_str::<Language>("\"\"").is_err());
+    }
+
+    #[test]
+    fn test_additional_languages() {
+        assert_eq!(Language::Bengali.to_string(), "bn");
+        assert_eq!("bn".to_string().iso_639().unwrap(), Language::Bengali);
+        assert_eq!(Language::Marathi.to_string(), "mr");
+        assert_eq!("mr".to_string().iso_639().unwrap(), Language::Marathi);
+        assert_eq!(Language::Telugu.to_string(), "te");
+        assert_eq!("te".to_string().iso_639().unwrap(), Language::Telugu);
+        assert_eq!(Language::Turkish.to_string(), "tr");
+        assert_eq!("tr".to_string().iso_639().unwrap(), Language::Turkish);
+        assert_eq!(Language::Tamil.to_string(), "ta");
+        assert_eq!("ta".to_string().iso_639().unwrap(), Language::Tamil);
+        assert_eq!(Language::Vietnamese.to_string(), "vi");
+        assert_eq!("vi".to_string().iso_639().unwrap(), Language::Vietnamese);
+        assert_eq!(Language::Korean.to_string(), "ko");
+        assert_eq!("ko".to_string().iso_639().unwrap(), Language::Korean);
+        assert_eq!(Language::Italian.to_string(), "it");
+        assert_eq!("it".to_string().iso_639().unwrap(), Language::Italian);
+        assert_eq!(Language::Punjabi.to_string(), "pa");
+        assert_eq!("pa".to_string().iso_639().unwrap(), Language::Punjabi);
+        assert_eq!(Language::Javanese.to_string(), "jv");
+        assert_eq!("jv".to_string().iso_639().unwrap(), Language::Javanese);
+        assert_eq!(Language::Persian.to_string(), "fa");
+        assert_eq!("fa".to_string().iso_639().unwrap(), Language::Persian);
+        assert_eq!(Language::Gujarati.to_string(), "gu");
+        assert_eq!("gu".to_string().iso_639().unwrap(), Language::Gujarati);
+        let languages: Vec<Language> =
+            serde_json::from_str(r#"["bn", "te", "tr", "ko", "vi"]"#).unwrap();
+        assert_eq!(
+            languages,
+            vec![
+                Language::Bengali,
+                Language::Telugu,
+                Language::Turkish,
+                Language::Korean,
+                Language::Vietnamese
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&languages).unwrap(),
+            r#"["bn","te","tr","ko","vi"]"#
+        );
+    }
+
     use crate::convert::{ipa_to_phonemes, phonemes_to_loan};
     #[test]
     fn test_language_to_latin() {
@@ -2,5 +2,6 @@ pub mod convert;
 pub mod language;
 pub mod phoneme;
 pub mod phonotactics;
+mod profile;
 pub mod recipe;
 pub mod word_generator;
@@ -1,13 +1,16 @@
 use bacitit_word_generator::convert;
-use bacitit_word_generator::phoneme::{Phoneme};
-use bacitit_word_generator::recipe::{Recipe};
+use bacitit_word_generator::phoneme::{self, Phoneme};
+use bacitit_word_generator::recipe::{Recipe, SuperLanguage};
 use bacitit_word_generator::word_generator::WordGenerator;
 use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
 use std::fs::File;
 use std::io::BufReader;
+use std::process;
 use std::{fs, io::Write};
 
-fn export_word(candidate_words: &WordGenerator) {
+fn render_word(candidate_words: &WordGenerator) -> String {
     let best_word = &candidate_words.words[0];
     let mut output = format!(
         "# {}\n\n## Meaning\n\n{}",
@@ -16,9 +19,8 @@ fn export_word(candidate_words: &WordGenerator) {
     );
     let candidates_info = {
         let mut s = "|Word|Score|\n|:-:|:-:|\n".to_string();
-        let b = candidate_words.words.iter().take(10);
+        let b = candidate_words.iter().take(10);
         for c in b {
-            println!("{:?}", c);
             s.push_str(&format!(
                 "|{}|{:.6}|\n",
                 convert::phonemes_to_loan(&c.word),
@@ -31,12 +33,11 @@ fn export_word(candidate_words: &WordGenerator) {
         let mut s = "|ISO 639-1|Weight|Regular weight|Origin word|IPA|Loanword|\n|:-:|:-:|:-:|:-:|:-:|:-:|\n"
             .to_string();
         for origin in &candidate_words.super_word.origins {
-            let language = &origin.language;
             s.push_str(&format!(
                 "|{}|{}|{:.4}|{}|{}|{}|\n",
-                language,
-                candidate_words.get_population(&language),
-                candidate_words.get_population(&language) / candidate_words.weight_sum,
+                origin.language,
+                candidate_words.origin_weight(origin),
+                candidate_words.origin_weight(origin) / candidate_words.weight_sum,
                 origin.word,
                 origin.ipa.as_ref().unwrap(),
                 convert::phonemes_to_loan(origin.loan.as_ref().unwrap()),
@@ -48,12 +49,22 @@ fn export_word(candidate_words: &WordGenerator) {
         "\n\n## Candidates\n\n{}\n## Origins\n\nWeight sum: {}\n{}",
         candidates_info, candidate_words.weight_sum, langs_info
     ));
+    output
+}
+
+fn export_word(candidate_words: &WordGenerator, quiet: bool) {
+    if !quiet {
+        for c in candidate_words.iter().take(10) {
+            println!("{}", c);
+        }
+    }
     let mut f = fs::File::create(format!(
         "./export/dic/{}.md",
-        convert::phonemes_to_loan(&best_word.word)
+        convert::phonemes_to_loan(&candidate_words.words[0].word)
     ))
     .unwrap();
-    f.write_all(output.as_bytes()).unwrap();
+    f.write_all(render_word(candidate_words).as_bytes())
+        .unwrap();
 }
 
 fn export_word_list(generated: &BTreeMap<String, Vec<Phoneme>>) {
@@ -72,31 +83,186 @@ fn export_word_list(generated: &BTreeMap<String, Vec<Phoneme>>) {
     f.write_all(output.as_bytes()).unwrap();
 }
 
+fn render_results(generated: &BTreeMap<String, Vec<Phoneme>>, json: bool) -> String {
+    if json {
+        let loans: BTreeMap<&String, String> = generated
+            .iter()
+            .map(|(meaning, word)| (meaning, convert::phonemes_to_loan(word)))
+            .collect();
+        format!("{}\n", serde_json::to_string(&loans).unwrap())
+    } else {
+        generated
+            .iter()
+            .map(|(meaning, word)| format!("{}\t{}\n", meaning, convert::phonemes_to_loan(word)))
+            .collect()
+    }
+}
+
+fn write_results(path: &str, results: &str) -> std::io::Result<()> {
+    let mut f = File::create(path)?;
+    f.write_all(results.as_bytes())
+}
+
+fn load_populations(path: &str) -> Result<Vec<SuperLanguage>, Box<dyn Error + Send + Sync>> {
+    SuperLanguage::from_csv(File::open(path)?)
+}
+
 fn export_result(recipe: Recipe) {
     serde_json::to_writer_pretty(&File::create("./data/result.json").unwrap(), &recipe).unwrap();
 }
 
+fn verify_recipe(recipe: Recipe) -> Result<String, Vec<String>> {
+    recipe.validate()?;
+    let recipe = recipe.complement();
+    Ok(format!(
+        "OK: {} languages, {} words, total population {}",
+        recipe.super_languages.len(),
+        recipe.super_words.len(),
+        recipe
+            .super_languages
+            .iter()
+            .map(|super_language| super_language.population)
+            .sum::<f64>()
+    ))
+}
+
+fn emit_schema() {
+    #[cfg(feature = "schema")]
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&schemars::schema_for!(Recipe)).unwrap()
+    );
+    #[cfg(not(feature = "schema"))]
+    {
+        eprintln!("--emit-schema requires the `schema` feature");
+        process::exit(1);
+    }
+}
+
+const SELF_TEST_RECIPE: &str = include_str!("../data/self-test.json");
+const SELF_TEST_EXPECTED: [(&str, &str, f64); 2] =
+    [("cat", "nekat", 2.75), ("lemon", "lemon", 3.75)];
+
+fn self_test() {
+    let recipe: Recipe = serde_json::from_str(SELF_TEST_RECIPE).unwrap();
+    if !run_self_test(recipe, &SELF_TEST_EXPECTED) {
+        process::exit(1);
+    }
+}
+
+fn run_self_test(recipe: Recipe, expected: &[(&str, &str, f64)]) -> bool {
+    let recipe = recipe.complement();
+    let mut passed = recipe.super_words.len() == expected.len();
+    if !passed {
+        println!(
+            "FAIL: the fixture has {} words, expected {}",
+            recipe.super_words.len(),
+            expected.len()
+        );
+    }
+    for (super_word, (meaning, word, score)) in recipe.super_words.iter().zip(expected.iter()) {
+        let mut word_generator =
+            WordGenerator::new(&recipe.super_languages, super_word.clone(), 1000000);
+        word_generator.phoneme_frequency = recipe.phoneme_frequency.clone();
+        word_generator.quiet = true;
+        word_generator.generate();
+        let best_word = &word_generator.words[0];
+        let actual = convert::phonemes_to_loan(&best_word.word);
+        let ok = super_word.meaning == *meaning
+            && actual == *word
+            && (best_word.score - score).abs() < 1e-9;
+        println!(
+            "{}: {} {} ({:.6}), expected {} ({:.6})",
+            if ok { "PASS" } else { "FAIL" },
+            super_word.meaning,
+            actual,
+            best_word.score,
+            word,
+            score
+        );
+        passed &= ok;
+    }
+    passed
+}
+
 pub fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.iter().any(|arg| arg == "--emit-schema") {
+        emit_schema();
+        return;
+    }
+    if args.iter().any(|arg| arg == "--self-test") {
+        self_test();
+        return;
+    }
+    if args.iter().any(|arg| arg == "--list-phonemes") {
+        print!("{}", phoneme::phoneme_table());
+        return;
+    }
+    let quiet = args
+        .iter()
+        .any(|arg| arg == "--quiet" || arg == "--result-only");
+    let output = match args.iter().position(|arg| arg == "--output") {
+        Some(i) => match args.get(i + 1) {
+            Some(path) => Some(path.clone()),
+            None => {
+                eprintln!("--output requires a path");
+                process::exit(1);
+            }
+        },
+        None => None,
+    };
     let recipe_file = File::open("data/recipe.json").unwrap();
     let recipe_reader = BufReader::new(recipe_file);
     let recipe: Recipe = serde_json::from_reader(recipe_reader).unwrap();
+    let recipe = match args.iter().position(|arg| arg == "--populations") {
+        Some(i) => match args.get(i + 1) {
+            Some(path) => match load_populations(path) {
+                Ok(populations) => recipe.override_populations(populations),
+                Err(e) => {
+                    eprintln!("failed to load populations from {}: {}", path, e);
+                    process::exit(1);
+                }
+            },
+            None => {
+                eprintln!("--populations requires a path");
+                process::exit(1);
+            }
+        },
+        None => recipe,
+    };
+    if args.iter().any(|arg| arg == "--verify-recipe") {
+        match verify_recipe(recipe) {
+            Ok(summary) => println!("{}", summary),
+            Err(errors) => {
+                for error in errors {
+                    eprintln!("{}", error);
+                }
+                process::exit(1);
+            }
+        }
+        return;
+    }
     let recipe = recipe.complement();
     let mut generated = BTreeMap::new();
-    println!("super_words.words.len() = {}", recipe.super_words.len());
+    if !quiet {
+        println!("super_words.words.len() = {}", recipe.super_words.len());
+    }
     for super_word in recipe.super_words.clone() {
-        let mut word_generator = WordGenerator {
-            super_languages: &recipe.super_languages,
-            super_word,
-            words: Vec::new(),
-            limit: 1000000,
-            weight_sum: 0.0,
-        };
-        println!(
-            "\nGenerating a word meaning '{}'...",
-            word_generator.super_word.meaning
-        );
+        let mut word_generator = WordGenerator::new(&recipe.super_languages, super_word, 1000000);
+        word_generator.phoneme_frequency = recipe.phoneme_frequency.clone();
+        word_generator.quiet = quiet;
+        if !quiet {
+            println!(
+                "\nGenerating a word meaning '{}'...",
+                word_generator.super_word.meaning
+            );
+        }
         word_generator.generate();
-        export_word(&word_generator);
+        if args.iter().any(|arg| arg == "--profile") {
+            println!("\n{}", word_generator.profile());
+        }
+        export_word(&word_generator, quiet);
         generated.insert(
             word_generator.super_word.meaning.clone(),
             (&word_generator.words[0].word).clone(),
@@ -104,4 +270,107 @@ pub fn main() {
         export_result(recipe.clone());
         export_word_list(&generated);
     }
+    let results = render_results(&generated, args.iter().any(|arg| arg == "--json"));
+    if let Some(path) = output {
+        if let Err(e) = write_results(&path, &results) {
+            eprintln!("failed to write {}: {}", path, e);
+            process::exit(1);
+        }
+    } else if quiet {
+        print!("{}", results);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_test() {
+        let recipe: Recipe = serde_json::from_str(SELF_TEST_RECIPE).unwrap();
+        assert!(run_self_test(recipe.clone(), &SELF_TEST_EXPECTED));
+        let mut truncated = recipe.clone();
+        truncated.super_words.truncate(1);
+        assert!(!run_self_test(truncated, &SELF_TEST_EXPECTED));
+        assert!(!run_self_test(recipe, &SELF_TEST_EXPECTED[..1]));
+    }
+
+    #[test]
+    fn test_render_word_golden() {
+        let recipe: Recipe = serde_json::from_str(SELF_TEST_RECIPE).unwrap();
+        let recipe = recipe.complement();
+        let golden = [
+            include_str!("../data/golden/nekat.md"),
+            include_str!("../data/golden/lemon.md"),
+        ];
+        for (super_word, golden) in recipe.super_words.iter().zip(golden.iter()) {
+            let mut word_generator =
+                WordGenerator::new(&recipe.super_languages, super_word.clone(), 1000000);
+            word_generator.quiet = true;
+            word_generator.generate();
+            assert_eq!(render_word(&word_generator), *golden);
+        }
+    }
+
+    #[test]
+    fn test_render_results() {
+        let mut generated = BTreeMap::new();
+        generated.insert("cat".to_string(), convert::loan_to_phonemes("nekat"));
+        generated.insert("lemon".to_string(), convert::loan_to_phonemes("lemon"));
+        let output = render_results(&generated, false);
+        assert_eq!(output.lines().count(), generated.len());
+        assert!(!output.contains('|'));
+        assert_eq!(output, "cat\tnekat\nlemon\tlemon\n");
+        assert_eq!(
+            render_results(&generated, true),
+            "{\"cat\":\"nekat\",\"lemon\":\"lemon\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_write_results() {
+        let path = env::temp_dir().join("bacitit-test-write-results.txt");
+        let path = path.to_str().unwrap();
+        fs::write(path, "stale contents that are longer than the results\n").unwrap();
+        write_results(path, "cat\tnekat\n").unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "cat\tnekat\n");
+        fs::remove_file(path).unwrap();
+
+        let missing = env::temp_dir().join("bacitit-no-such-dir").join("out.txt");
+        assert!(write_results(missing.to_str().unwrap(), "").is_err());
+    }
+
+    #[test]
+    fn test_load_populations() {
+        let path = env::temp_dir().join("bacitit-test-load-populations.csv");
+        let path = path.to_str().unwrap();
+        fs::write(path, "language,population\nen,818.9\n").unwrap();
+        let populations = load_populations(path).unwrap();
+        assert_eq!(populations.len(), 1);
+        assert_eq!(populations[0].population, 818.9);
+        fs::write(path, "language,population\nxx,1\n").unwrap();
+        assert_eq!(
+            load_populations(path).unwrap_err().to_string(),
+            "unknown language: xx"
+        );
+        fs::remove_file(path).unwrap();
+        assert!(load_populations(path).is_err());
+    }
+
+    #[test]
+    fn test_verify_recipe() {
+        let mut recipe: Recipe =
+            serde_json::from_str(include_str!("../data/invalid-recipe.json")).unwrap();
+        assert_eq!(
+            verify_recipe(recipe.clone()),
+            Err(vec![
+                "'cat' has an origin in ja which is not a super language".to_string()
+            ])
+        );
+        recipe.super_words[0].origins.pop();
+        assert_eq!(
+            verify_recipe(recipe),
+            Ok("OK: 1 languages, 1 words, total population 2".to_string())
+        );
+    }
 }
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub enum Phoneme {
     A,
     B,
@@ -30,9 +30,72 @@ pub enum Phoneme {
     SCHWA,
 }
 
+impl Phoneme {
+    // Declaration order, which is also the `Ord` order. Do not reorder.
+    pub const ALL: [Phoneme; 26] = [
+        Phoneme::A,
+        Phoneme::B,
+        Phoneme::C,
+        Phoneme::D,
+        Phoneme::E,
+        Phoneme::F,
+        Phoneme::G,
+        Phoneme::H,
+        Phoneme::I,
+        Phoneme::J,
+        Phoneme::K,
+        Phoneme::L,
+        Phoneme::M,
+        Phoneme::N,
+        Phoneme::O,
+        Phoneme::P,
+        Phoneme::R,
+        Phoneme::S,
+        Phoneme::T,
+        Phoneme::U,
+        Phoneme::V,
+        Phoneme::W,
+        Phoneme::X,
+        Phoneme::Y,
+        Phoneme::Z,
+        Phoneme::SCHWA,
+    ];
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Script {
+    Latin,
+    Cyrillic,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VowelClass {
+    Front,
+    Back,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Manner {
+    Vowel,
+    Plosive,
+    Nasal,
+    Fricative,
+    Trill,
+    Lateral,
+    Approximant,
+}
+
 pub trait PhonemeExt {
     fn is_vowel(&self) -> bool;
     fn is_consonant(&self) -> bool;
+    fn to_latin(&self) -> &'static str;
+    fn to_cyrillic(&self) -> &'static str;
+    fn to_ipa(&self) -> &'static str;
+    fn render(&self, script: Script) -> &'static str;
+    fn voiced_pair(&self) -> Option<Phoneme>;
+    fn vowel_class(&self) -> Option<VowelClass>;
+    fn is_voiced(&self) -> bool;
+    fn manner(&self) -> Manner;
 }
 
 impl PhonemeExt for Phoneme {
@@ -68,4 +131,345 @@ impl PhonemeExt for Phoneme {
             _ => false,
         }
     }
+
+    fn to_latin(&self) -> &'static str {
+        match *self {
+            Self::P => "p",
+            Self::B => "b",
+            Self::T => "t",
+            Self::D => "d",
+            Self::K => "k",
+            Self::G => "g",
+            Self::M => "m",
+            Self::N => "n",
+            Self::R => "r",
+            Self::F => "f",
+            Self::V => "v",
+            Self::S => "s",
+            Self::Z => "z",
+            Self::C => "c",
+            Self::J => "j",
+            Self::X => "x",
+            Self::H => "h",
+            Self::Y => "y",
+            Self::L => "l",
+            Self::W => "w",
+            Self::I => "i",
+            Self::U => "u",
+            Self::E => "e",
+            Self::SCHWA => "-",
+            Self::O => "o",
+            Self::A => "a",
+        }
+    }
+
+    fn to_cyrillic(&self) -> &'static str {
+        match *self {
+            Self::P => "п",
+            Self::B => "б",
+            Self::T => "т",
+            Self::D => "д",
+            Self::K => "к",
+            Self::G => "г",
+            Self::M => "м",
+            Self::N => "н",
+            Self::R => "р",
+            Self::F => "ф",
+            Self::V => "в",
+            Self::S => "с",
+            Self::Z => "з",
+            Self::C => "ш",
+            Self::J => "ж",
+            Self::X => "х",
+            Self::H => "һ",
+            Self::Y => "й",
+            Self::L => "л",
+            Self::W => "ў",
+            Self::I => "и",
+            Self::U => "у",
+            Self::E => "э",
+            Self::SCHWA => "ъ",
+            Self::O => "о",
+            Self::A => "а",
+        }
+    }
+
+    fn to_ipa(&self) -> &'static str {
+        match *self {
+            Self::P => "p",
+            Self::B => "b",
+            Self::T => "t",
+            Self::D => "d",
+            Self::K => "k",
+            Self::G => "ɡ",
+            Self::M => "m",
+            Self::N => "n",
+            Self::R => "r",
+            Self::F => "f",
+            Self::V => "v",
+            Self::S => "s",
+            Self::Z => "z",
+            Self::C => "ʃ",
+            Self::J => "ʒ",
+            Self::X => "x",
+            Self::H => "h",
+            Self::Y => "j",
+            Self::L => "l",
+            Self::W => "w",
+            Self::I => "i",
+            Self::U => "u",
+            Self::E => "e",
+            Self::SCHWA => "ə",
+            Self::O => "o",
+            Self::A => "a",
+        }
+    }
+
+    fn render(&self, script: Script) -> &'static str {
+        match script {
+            Script::Latin => self.to_latin(),
+            Script::Cyrillic => self.to_cyrillic(),
+        }
+    }
+
+    fn voiced_pair(&self) -> Option<Phoneme> {
+        match *self {
+            Self::P => Some(Self::B),
+            Self::B => Some(Self::P),
+            Self::T => Some(Self::D),
+            Self::D => Some(Self::T),
+            Self::K => Some(Self::G),
+            Self::G => Some(Self::K),
+            Self::F => Some(Self::V),
+            Self::V => Some(Self::F),
+            Self::S => Some(Self::Z),
+            Self::Z => Some(Self::S),
+            Self::C => Some(Self::J),
+            Self::J => Some(Self::C),
+            _ => None,
+        }
+    }
+
+    fn vowel_class(&self) -> Option<VowelClass> {
+        match *self {
+            Self::I | Self::E => Some(VowelClass::Front),
+            Self::A | Self::O | Self::U => Some(VowelClass::Back),
+            _ => None,
+        }
+    }
+
+    fn is_voiced(&self) -> bool {
+        !matches!(
+            *self,
+            Self::P | Self::T | Self::K | Self::F | Self::S | Self::C | Self::X | Self::H
+        )
+    }
+
+    fn manner(&self) -> Manner {
+        match *self {
+            Self::P | Self::B | Self::T | Self::D | Self::K | Self::G => Manner::Plosive,
+            Self::M | Self::N => Manner::Nasal,
+            Self::F | Self::V | Self::S | Self::Z | Self::C | Self::J | Self::X | Self::H => {
+                Manner::Fricative
+            }
+            Self::R => Manner::Trill,
+            Self::L => Manner::Lateral,
+            Self::Y | Self::W => Manner::Approximant,
+            Self::A | Self::E | Self::I | Self::O | Self::U | Self::SCHWA => Manner::Vowel,
+        }
+    }
+}
+
+pub fn phoneme_table() -> String {
+    let mut s = "|Letter|IPA|Class|Voicing|\n|:-:|:-:|:-:|:-:|\n".to_string();
+    for phoneme in Phoneme::ALL.iter() {
+        let class = match phoneme.manner() {
+            Manner::Vowel if !phoneme.is_vowel() => "reduced vowel",
+            Manner::Vowel => "vowel",
+            Manner::Plosive => "plosive",
+            Manner::Nasal => "nasal",
+            Manner::Fricative => "fricative",
+            Manner::Trill => "trill",
+            Manner::Lateral => "lateral",
+            Manner::Approximant => "approximant",
+        };
+        s.push_str(&format!(
+            "|{}|{}|{}|{}|\n",
+            phoneme.to_latin(),
+            phoneme.to_ipa(),
+            class,
+            if phoneme.is_voiced() {
+                "voiced"
+            } else {
+                "voiceless"
+            }
+        ));
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_latin() {
+        assert_eq!(Phoneme::A.to_latin(), "a");
+        assert_eq!(Phoneme::B.to_latin(), "b");
+        assert_eq!(Phoneme::C.to_latin(), "c");
+        assert_eq!(Phoneme::D.to_latin(), "d");
+        assert_eq!(Phoneme::E.to_latin(), "e");
+        assert_eq!(Phoneme::F.to_latin(), "f");
+        assert_eq!(Phoneme::G.to_latin(), "g");
+        assert_eq!(Phoneme::H.to_latin(), "h");
+        assert_eq!(Phoneme::I.to_latin(), "i");
+        assert_eq!(Phoneme::J.to_latin(), "j");
+        assert_eq!(Phoneme::K.to_latin(), "k");
+        assert_eq!(Phoneme::L.to_latin(), "l");
+        assert_eq!(Phoneme::M.to_latin(), "m");
+        assert_eq!(Phoneme::N.to_latin(), "n");
+        assert_eq!(Phoneme::O.to_latin(), "o");
+        assert_eq!(Phoneme::P.to_latin(), "p");
+        assert_eq!(Phoneme::R.to_latin(), "r");
+        assert_eq!(Phoneme::S.to_latin(), "s");
+        assert_eq!(Phoneme::T.to_latin(), "t");
+        assert_eq!(Phoneme::U.to_latin(), "u");
+        assert_eq!(Phoneme::V.to_latin(), "v");
+        assert_eq!(Phoneme::W.to_latin(), "w");
+        assert_eq!(Phoneme::X.to_latin(), "x");
+        assert_eq!(Phoneme::Y.to_latin(), "y");
+        assert_eq!(Phoneme::Z.to_latin(), "z");
+        assert_eq!(Phoneme::SCHWA.to_latin(), "-");
+    }
+
+    #[test]
+    fn test_to_cyrillic() {
+        assert_eq!(Phoneme::A.render(Script::Cyrillic), "а");
+        assert_eq!(Phoneme::B.render(Script::Cyrillic), "б");
+        assert_eq!(Phoneme::C.render(Script::Cyrillic), "ш");
+        assert_eq!(Phoneme::D.render(Script::Cyrillic), "д");
+        assert_eq!(Phoneme::E.render(Script::Cyrillic), "э");
+        assert_eq!(Phoneme::F.render(Script::Cyrillic), "ф");
+        assert_eq!(Phoneme::G.render(Script::Cyrillic), "г");
+        assert_eq!(Phoneme::H.render(Script::Cyrillic), "һ");
+        assert_eq!(Phoneme::I.render(Script::Cyrillic), "и");
+        assert_eq!(Phoneme::J.render(Script::Cyrillic), "ж");
+        assert_eq!(Phoneme::K.render(Script::Cyrillic), "к");
+        assert_eq!(Phoneme::L.render(Script::Cyrillic), "л");
+        assert_eq!(Phoneme::M.render(Script::Cyrillic), "м");
+        assert_eq!(Phoneme::N.render(Script::Cyrillic), "н");
+        assert_eq!(Phoneme::O.render(Script::Cyrillic), "о");
+        assert_eq!(Phoneme::P.render(Script::Cyrillic), "п");
+        assert_eq!(Phoneme::R.render(Script::Cyrillic), "р");
+        assert_eq!(Phoneme::S.render(Script::Cyrillic), "с");
+        assert_eq!(Phoneme::SCHWA.render(Script::Cyrillic), "ъ");
+        assert_eq!(Phoneme::T.render(Script::Cyrillic), "т");
+        assert_eq!(Phoneme::U.render(Script::Cyrillic), "у");
+        assert_eq!(Phoneme::V.render(Script::Cyrillic), "в");
+        assert_eq!(Phoneme::W.render(Script::Cyrillic), "ў");
+        assert_eq!(Phoneme::X.render(Script::Cyrillic), "х");
+        assert_eq!(Phoneme::Y.render(Script::Cyrillic), "й");
+        assert_eq!(Phoneme::Z.render(Script::Cyrillic), "з");
+        assert_eq!(Phoneme::A.render(Script::Latin), "a");
+    }
+
+    #[test]
+    fn test_vowel_class() {
+        assert_eq!(Phoneme::I.vowel_class(), Some(VowelClass::Front));
+        assert_eq!(Phoneme::E.vowel_class(), Some(VowelClass::Front));
+        assert_eq!(Phoneme::A.vowel_class(), Some(VowelClass::Back));
+        assert_eq!(Phoneme::O.vowel_class(), Some(VowelClass::Back));
+        assert_eq!(Phoneme::U.vowel_class(), Some(VowelClass::Back));
+        assert_eq!(Phoneme::SCHWA.vowel_class(), None);
+        assert_eq!(Phoneme::Y.vowel_class(), None);
+    }
+
+    #[test]
+    fn test_voiced_pair() {
+        assert_eq!(Phoneme::P.voiced_pair(), Some(Phoneme::B));
+        assert_eq!(Phoneme::B.voiced_pair(), Some(Phoneme::P));
+        assert_eq!(Phoneme::T.voiced_pair(), Some(Phoneme::D));
+        assert_eq!(Phoneme::D.voiced_pair(), Some(Phoneme::T));
+        assert_eq!(Phoneme::K.voiced_pair(), Some(Phoneme::G));
+        assert_eq!(Phoneme::G.voiced_pair(), Some(Phoneme::K));
+        assert_eq!(Phoneme::F.voiced_pair(), Some(Phoneme::V));
+        assert_eq!(Phoneme::V.voiced_pair(), Some(Phoneme::F));
+        assert_eq!(Phoneme::S.voiced_pair(), Some(Phoneme::Z));
+        assert_eq!(Phoneme::Z.voiced_pair(), Some(Phoneme::S));
+        assert_eq!(Phoneme::C.voiced_pair(), Some(Phoneme::J));
+        assert_eq!(Phoneme::J.voiced_pair(), Some(Phoneme::C));
+        assert_eq!(Phoneme::M.voiced_pair(), None);
+        assert_eq!(Phoneme::N.voiced_pair(), None);
+        assert_eq!(Phoneme::R.voiced_pair(), None);
+        assert_eq!(Phoneme::L.voiced_pair(), None);
+        assert_eq!(Phoneme::X.voiced_pair(), None);
+        assert_eq!(Phoneme::H.voiced_pair(), None);
+        assert_eq!(Phoneme::Y.voiced_pair(), None);
+        assert_eq!(Phoneme::W.voiced_pair(), None);
+        assert_eq!(Phoneme::A.voiced_pair(), None);
+        assert_eq!(Phoneme::E.voiced_pair(), None);
+        assert_eq!(Phoneme::I.voiced_pair(), None);
+        assert_eq!(Phoneme::O.voiced_pair(), None);
+        assert_eq!(Phoneme::U.voiced_pair(), None);
+        assert_eq!(Phoneme::SCHWA.voiced_pair(), None);
+    }
+
+    #[test]
+    fn test_all_order() {
+        let latin: Vec<&str> = Phoneme::ALL.iter().map(|p| p.to_latin()).collect();
+        assert_eq!(latin.concat(), "abcdefghijklmnoprstuvwxyz-");
+        let mut sorted = Phoneme::ALL;
+        sorted.sort();
+        assert_eq!(sorted, Phoneme::ALL);
+        for (i, phoneme) in Phoneme::ALL.iter().enumerate() {
+            assert_eq!(*phoneme as usize, i);
+        }
+    }
+
+    #[test]
+    fn test_is_voiced() {
+        for phoneme in Phoneme::ALL.iter() {
+            if let Some(pair) = phoneme.voiced_pair() {
+                assert_ne!(phoneme.is_voiced(), pair.is_voiced());
+            }
+        }
+        assert!(Phoneme::B.is_voiced());
+        assert!(!Phoneme::P.is_voiced());
+        assert!(Phoneme::M.is_voiced());
+        assert!(!Phoneme::H.is_voiced());
+        assert!(Phoneme::A.is_voiced());
+    }
+
+    #[test]
+    fn test_manner() {
+        assert_eq!(Phoneme::P.manner(), Manner::Plosive);
+        assert_eq!(Phoneme::N.manner(), Manner::Nasal);
+        assert_eq!(Phoneme::C.manner(), Manner::Fricative);
+        assert_eq!(Phoneme::R.manner(), Manner::Trill);
+        assert_eq!(Phoneme::L.manner(), Manner::Lateral);
+        assert_eq!(Phoneme::W.manner(), Manner::Approximant);
+        assert_eq!(Phoneme::SCHWA.manner(), Manner::Vowel);
+        for phoneme in Phoneme::ALL.iter() {
+            assert_eq!(phoneme.manner() == Manner::Vowel, !phoneme.is_consonant());
+        }
+    }
+
+    #[test]
+    fn test_phoneme_table() {
+        let table = phoneme_table();
+        let rows: Vec<&str> = table.lines().skip(2).collect();
+        assert_eq!(rows.len(), Phoneme::ALL.len());
+        for phoneme in Phoneme::ALL.iter() {
+            let prefix = format!("|{}|{}|", phoneme.to_latin(), phoneme.to_ipa());
+            assert_eq!(
+                rows.iter().filter(|row| row.starts_with(&prefix)).count(),
+                1
+            );
+        }
+        assert!(rows.contains(&"|a|a|vowel|voiced|"));
+        assert!(rows.contains(&"|b|b|plosive|voiced|"));
+        assert!(rows.contains(&"|c|ʃ|fricative|voiceless|"));
+        assert!(rows.contains(&"|y|j|approximant|voiced|"));
+        assert!(rows.contains(&"|-|ə|reduced vowel|voiced|"));
+    }
 }
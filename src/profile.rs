@@ -0,0 +1,53 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Default)]
+pub(crate) struct Profile {
+    phases: Vec<(String, Duration)>,
+}
+
+impl Profile {
+    pub fn time<T>(&mut self, phase: &str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        let elapsed = start.elapsed();
+        match self.phases.iter_mut().find(|(name, _)| name == phase) {
+            Some((_, duration)) => *duration += elapsed,
+            None => self.phases.push((phase.to_string(), elapsed)),
+        }
+        result
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "|Phase|Time (s)|\n|:-:|:-:|")?;
+        for (phase, duration) in &self.phases {
+            writeln!(f, "|{}|{:.6}|", phase, duration.as_secs_f64())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile() {
+        let mut profile = Profile::default();
+        assert_eq!(profile.time("initialize", || 1), 1);
+        profile.time("candidates", || {});
+        profile.time("enumerate", || {});
+        profile.time("candidates", || {});
+        profile.time("enumerate", || {});
+        let phases: Vec<&str> = profile.phases.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(phases, vec!["initialize", "candidates", "enumerate"]);
+        let output = profile.to_string();
+        for phase in phases {
+            assert!(output.contains(&format!("|{}|", phase)));
+        }
+    }
+}
@@ -1,37 +1,262 @@
 use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
 use serde::ser::{SerializeStruct, Serializer};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
 use std::fmt;
+use std::io::Read;
 
 use crate::convert;
+use crate::language::{Language, StringExt};
 use crate::phoneme::Phoneme;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Recipe {
     pub super_languages: Vec<SuperLanguage>,
     pub super_words: Vec<SuperWord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_population: Option<f64>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub phoneme_frequency: HashMap<Phoneme, f64>,
 }
 
 impl Recipe {
     pub fn complement(self) -> Self {
-        Recipe {
-            super_words: self
+        let (recipe, defaulted) = self.complement_with_defaults();
+        warn_defaulted(&defaulted);
+        recipe
+    }
+
+    fn complement_with_defaults(self) -> (Self, Vec<String>) {
+        let (recipe, defaulted) = self.complement_populations();
+        let recipe = Recipe {
+            super_words: recipe
                 .super_words
                 .iter()
                 .map(|super_word| super_word.clone().complement())
                 .collect(),
+            ..recipe
+        };
+        (recipe, defaulted)
+    }
+
+    fn complement_populations(self) -> (Self, Vec<String>) {
+        let mut super_languages = self.super_languages;
+        let mut defaulted = Vec::new();
+        for super_word in &self.super_words {
+            for origin in &super_word.origins {
+                if super_languages
+                    .iter()
+                    .any(|super_language| super_language.language == origin.language)
+                {
+                    continue;
+                }
+                match self.default_population {
+                    Some(population) => {
+                        super_languages.push(SuperLanguage {
+                            language: origin.language.clone(),
+                            population,
+                        });
+                        defaulted.push(origin.language.clone());
+                    }
+                    None => panic!(
+                        "人口が指定されていません。 Word: {} Language: {}",
+                        origin.word, origin.language
+                    ),
+                }
+            }
+        }
+        (
+            Recipe {
+                super_languages,
+                ..self
+            },
+            defaulted,
+        )
+    }
+
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        for super_language in &self.super_languages {
+            if let None | Some(Language::Other(_)) = super_language.language.iso_639() {
+                errors.push(format!("unknown language: {}", super_language.language));
+            }
+            if super_language.population <= 0.0 || super_language.population.is_nan() {
+                errors.push(format!(
+                    "population of {} is not positive: {}",
+                    super_language.language, super_language.population
+                ));
+            }
+        }
+        for super_word in &self.super_words {
+            if super_word.origins.is_empty() {
+                errors.push(format!("'{}' has no origins", super_word.meaning));
+            }
+            for origin in &super_word.origins {
+                if self.default_population.is_none()
+                    && !self
+                        .super_languages
+                        .iter()
+                        .any(|super_language| super_language.language == origin.language)
+                {
+                    errors.push(format!(
+                        "'{}' has an origin in {} which is not a super language",
+                        super_word.meaning, origin.language
+                    ));
+                }
+                if let Some(recency) = origin.recency {
+                    if recency <= 0.0 || recency.is_nan() {
+                        errors.push(format!(
+                            "recency of '{}' from {} must be positive: {}",
+                            super_word.meaning, origin.language, recency
+                        ));
+                    }
+                }
+                if origin.ipa.is_none() && !convert::has_ipa_source(&origin.language) {
+                    errors.push(format!(
+                        "'{}' from {} has no IPA and there is no IPA source for {}",
+                        super_word.meaning, origin.language, origin.language
+                    ));
+                }
+                let loan = match (&origin.loan, &origin.ipa) {
+                    (Some(loan), _) => Some(loan.clone()),
+                    (None, Some(ipa)) => Some(convert::ipa_to_phonemes(ipa)),
+                    (None, None) => None,
+                };
+                if let Some(loan) = loan {
+                    if loan.contains(&Phoneme::SCHWA) {
+                        errors.push(format!(
+                            "'{}' has a loan from {} containing a schwa",
+                            super_word.meaning, origin.language
+                        ));
+                    }
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn override_populations(self, populations: Vec<SuperLanguage>) -> Self {
+        let mut super_languages = self.super_languages;
+        for population in populations {
+            match super_languages
+                .iter_mut()
+                .find(|super_language| super_language.language == population.language)
+            {
+                Some(super_language) => super_language.population = population.population,
+                None => super_languages.push(population),
+            }
+        }
+        Recipe {
+            super_languages,
             ..self
         }
     }
+
+    pub fn phonemes_from_language(&self, language: &str) -> HashMap<Phoneme, f64> {
+        let mut map = HashMap::new();
+        for super_word in &self.super_words {
+            let weight_sum: f64 = super_word
+                .origins
+                .iter()
+                .map(|origin| origin.weight(&self.super_languages))
+                .sum();
+            for origin in &super_word.origins {
+                if origin.language != language {
+                    continue;
+                }
+                let weight = origin.weight(&self.super_languages) / weight_sum;
+                let phonemes: BTreeSet<&Phoneme> = origin.loan.as_ref().unwrap().iter().collect();
+                for phoneme in phonemes {
+                    *map.entry(*phoneme).or_insert(0.0) += weight;
+                }
+            }
+        }
+        map
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn from_async_reader(
+        mut reader: impl tokio::io::AsyncRead + Unpin,
+    ) -> Result<Recipe, Box<dyn Error + Send + Sync>> {
+        use tokio::io::AsyncReadExt;
+        let mut buffer = Vec::new();
+        reader.read_to_end(&mut buffer).await?;
+        Ok(serde_json::from_slice(&buffer)?)
+    }
+}
+
+fn warn_defaulted(defaulted: &[String]) {
+    if !defaulted.is_empty() {
+        eprintln!(
+            "既定の人口を使用しました。 Languages: {}",
+            defaulted.join(", ")
+        );
+    }
+}
+
+// Complemented recipes keyed on the raw recipe's JSON.
+#[derive(Debug, Default)]
+pub struct ComplementCache {
+    recipes: HashMap<String, (Recipe, Vec<String>)>,
+    pub misses: usize,
+}
+
+impl ComplementCache {
+    pub fn complement(&mut self, recipe: Recipe) -> Recipe {
+        let key = serde_json::to_string(&recipe).unwrap();
+        if !self.recipes.contains_key(&key) {
+            self.misses += 1;
+            self.recipes
+                .insert(key.clone(), recipe.complement_with_defaults());
+        }
+        let (recipe, defaulted) = &self.recipes[&key];
+        warn_defaulted(defaulted);
+        recipe.clone()
+    }
+
+    pub fn clear(&mut self) {
+        self.recipes.clear();
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SuperLanguage {
     pub language: String,
     pub population: f64,
 }
 
+// The population of `language` among `super_languages`.
+pub(crate) fn population(super_languages: &[SuperLanguage], language: &str) -> f64 {
+    super_languages
+        .iter()
+        .find(|super_language| super_language.language == language)
+        .unwrap()
+        .population
+}
+
+impl SuperLanguage {
+    pub fn from_csv(reader: impl Read) -> Result<Vec<SuperLanguage>, Box<dyn Error + Send + Sync>> {
+        let mut super_languages = Vec::new();
+        for record in csv::Reader::from_reader(reader).deserialize() {
+            let super_language: SuperLanguage = record?;
+            if let None | Some(Language::Other(_)) = super_language.language.iso_639() {
+                return Err(format!("unknown language: {}", super_language.language).into());
+            }
+            super_languages.push(super_language);
+        }
+        Ok(super_languages)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SuperWord {
     pub id: usize,
     pub meaning: String,
@@ -58,14 +283,23 @@ impl SuperWord {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Origin {
     pub language: String,
     pub word: String,
     pub ipa: Option<String>,
+    // Serialized as a romanized loan string, not as a list of phonemes.
+    #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
     pub loan: Option<Vec<Phoneme>>,
+    pub recency: Option<f64>,
 }
 
 impl Origin {
+    // The population of the origin's language scaled by its recency.
+    pub fn weight(&self, super_languages: &[SuperLanguage]) -> f64 {
+        population(super_languages, &self.language) * self.recency.unwrap_or(1.0)
+    }
+
     pub fn complement_ipa(self) -> Self {
         match self.ipa {
             Some(_) => self,
@@ -91,8 +325,10 @@ impl Origin {
                     ..self
                 },
                 None => panic!(
-                    "借用語に変換できませんでした。 Word: {} Language: {} IPA: {:?}",
-                    self.word, self.language, self.ipa
+                    "借用語に変換できませんでした。 Word: {} Language: {} IPA: {}",
+                    self.word,
+                    self.language,
+                    self.ipa.as_deref().unwrap_or("")
                 ),
             },
         }
@@ -105,8 +341,11 @@ impl Origin {
     pub fn check_complement(self) -> Self {
         if self.contains_schwa() {
             panic!(
-                "əが含まれています。 Word: {} Language: {} IPA: {:?}, loan {:?}",
-                self.word, self.language, self.ipa, self.loan
+                "əが含まれています。 Word: {} Language: {} IPA: {}, loan {}",
+                self.word,
+                self.language,
+                self.ipa.as_deref().unwrap_or(""),
+                convert::phonemes_to_loan(self.loan.as_ref().unwrap())
             );
         }
         self
@@ -118,7 +357,7 @@ impl Serialize for Origin {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("Origin", 4)?;
+        let mut state = serializer.serialize_struct("Origin", 5)?;
         state.serialize_field("language", &self.language)?;
         state.serialize_field("word", &self.word)?;
         state.serialize_field("ipa", &self.ipa)?;
@@ -129,6 +368,10 @@ impl Serialize for Origin {
                 None => None,
             },
         )?;
+        match self.recency {
+            Some(recency) => state.serialize_field("recency", &recency)?,
+            None => state.skip_field("recency")?,
+        }
         state.end()
     }
 }
@@ -143,6 +386,7 @@ impl<'de> serde::de::Deserialize<'de> for Origin {
             Word,
             IPA,
             Loan,
+            Recency,
         }
 
         impl<'de> serde::de::Deserialize<'de> for Field {
@@ -156,7 +400,7 @@ impl<'de> serde::de::Deserialize<'de> for Origin {
                     type Value = Field;
 
                     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                        formatter.write_str("`language`, `word`, `ipa` or `loan`")
+                        formatter.write_str("`language`, `word`, `ipa`, `loan` or `recency`")
                     }
 
                     fn visit_str<E>(self, value: &str) -> Result<Field, E>
@@ -168,6 +412,7 @@ impl<'de> serde::de::Deserialize<'de> for Origin {
                             "word" => Ok(Field::Word),
                             "ipa" => Ok(Field::IPA),
                             "loan" => Ok(Field::Loan),
+                            "recency" => Ok(Field::Recency),
                             _ => Err(de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -202,11 +447,13 @@ impl<'de> serde::de::Deserialize<'de> for Origin {
                 let loan = seq
                     .next_element()?
                     .ok_or_else(|| de::Error::invalid_length(3, &self))?;
+                let recency = seq.next_element()?.unwrap_or(None);
                 Ok(Origin {
                     language,
                     word,
                     ipa,
                     loan,
+                    recency,
                 })
             }
 
@@ -218,6 +465,7 @@ impl<'de> serde::de::Deserialize<'de> for Origin {
                 let mut word = None;
                 let mut ipa = None;
                 let mut loan = None;
+                let mut recency = None;
                 while let Some(key) = map.next_key()? {
                     match key {
                         Field::Language => {
@@ -247,6 +495,12 @@ impl<'de> serde::de::Deserialize<'de> for Origin {
                                 None => None,
                             });
                         }
+                        Field::Recency => {
+                            if recency.is_some() {
+                                return Err(de::Error::duplicate_field("recency"));
+                            }
+                            recency = Some(map.next_value::<Option<f64>>()?);
+                        }
                     }
                 }
                 let language = language.ok_or_else(|| de::Error::missing_field("language"))?;
@@ -259,16 +513,289 @@ impl<'de> serde::de::Deserialize<'de> for Origin {
                     Some(s) => s,
                     None => None,
                 };
+                let recency = recency.unwrap_or(None);
                 Ok(Origin {
                     language,
                     word,
                     ipa,
                     loan,
+                    recency,
                 })
             }
         }
 
-        const FIELDS: &'static [&'static str] = &["language", "word", "ipa", "loan"];
+        const FIELDS: &'static [&'static str] = &["language", "word", "ipa", "loan", "recency"];
         deserializer.deserialize_struct("Duration", FIELDS, DurationVisitor)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn origin(language: &str, loan: &str) -> Origin {
+        Origin {
+            language: language.into(),
+            word: loan.into(),
+            ipa: None,
+            loan: Some(convert::loan_to_phonemes(loan)),
+            recency: None,
+        }
+    }
+
+    fn recipe() -> Recipe {
+        Recipe {
+            super_languages: vec![
+                SuperLanguage {
+                    language: "en".into(),
+                    population: 3.0,
+                },
+                SuperLanguage {
+                    language: "es".into(),
+                    population: 1.0,
+                },
+            ],
+            super_words: vec![
+                SuperWord {
+                    id: 0,
+                    meaning: "cat".into(),
+                    origins: vec![origin("en", "kat"), origin("es", "gato")],
+                },
+                SuperWord {
+                    id: 1,
+                    meaning: "tea".into(),
+                    origins: vec![origin("en", "ti")],
+                },
+            ],
+            default_population: None,
+            phoneme_frequency: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_default_population() {
+        use crate::word_generator::WordGenerator;
+
+        let mut recipe = recipe();
+        recipe.default_population = Some(2.0);
+        recipe.super_words[1].origins.push(origin("ja", "tit"));
+        for super_word in &mut recipe.super_words {
+            for origin in &mut super_word.origins {
+                origin.ipa = Some(origin.word.clone());
+            }
+        }
+        let recipe = recipe.complement();
+        assert_eq!(population(&recipe.super_languages, "en"), 3.0);
+        assert_eq!(population(&recipe.super_languages, "ja"), 2.0);
+        assert_eq!(recipe.super_languages.len(), 3);
+        let mut word_generator =
+            WordGenerator::new(&recipe.super_languages, recipe.super_words[1].clone(), 1000);
+        word_generator.generate();
+        assert_eq!(word_generator.weight_sum, 5.0);
+        assert!(!word_generator.words.is_empty());
+    }
+
+    #[test]
+    fn test_complement_cache() {
+        let mut recipe = recipe();
+        for super_word in &mut recipe.super_words {
+            for origin in &mut super_word.origins {
+                origin.ipa = Some(origin.word.clone());
+            }
+        }
+        let mut cache = ComplementCache::default();
+        let first = cache.complement(recipe.clone());
+        assert_eq!(cache.misses, 1);
+        let second = cache.complement(recipe.clone());
+        assert_eq!(cache.misses, 1);
+        assert_eq!(
+            serde_json::to_string(&first).unwrap(),
+            serde_json::to_string(&second).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_string(&first).unwrap(),
+            serde_json::to_string(&recipe.clone().complement()).unwrap()
+        );
+        recipe.super_languages[0].population = 4.0;
+        cache.complement(recipe.clone());
+        assert_eq!(cache.misses, 2);
+        cache.clear();
+        cache.complement(recipe);
+        assert_eq!(cache.misses, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_missing_population() {
+        let mut recipe = recipe();
+        recipe.super_words[1].origins.push(origin("ja", "ti"));
+        recipe.complement();
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_schema() {
+        let schema = serde_json::to_string(&schemars::schema_for!(Recipe)).unwrap();
+        let schema: serde_json::Value = serde_json::from_str(&schema).unwrap();
+        let origin = &schema["definitions"]["Origin"]["properties"];
+        assert_eq!(origin["language"]["type"], "string");
+        assert_eq!(
+            origin["loan"]["type"],
+            serde_json::json!(["string", "null"])
+        );
+        assert!(schema["properties"]["super_words"].is_object());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_from_async_reader() {
+        let json = include_bytes!("../data/recipe.json");
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let parsed = runtime
+            .block_on(Recipe::from_async_reader(&json[..]))
+            .unwrap();
+        let expected: Recipe = serde_json::from_slice(json).unwrap();
+        assert_eq!(
+            serde_json::to_string(&parsed).unwrap(),
+            serde_json::to_string(&expected).unwrap()
+        );
+        let error = runtime
+            .block_on(Recipe::from_async_reader(&b"{"[..]))
+            .unwrap_err();
+        let error = runtime
+            .block_on(runtime.spawn(async move { error }))
+            .unwrap();
+        assert!(error.to_string().contains("EOF"));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(recipe().validate(), Ok(()));
+        let mut recipe = recipe();
+        recipe.super_languages[1].population = 0.0;
+        recipe.super_words[1].origins.push(origin("ja", "ocya"));
+        recipe.super_words[1].origins.push(origin("es", "te-"));
+        recipe.super_words[1].origins.push(origin("es", ""));
+        recipe.super_words.push(SuperWord {
+            id: 2,
+            meaning: "dog".into(),
+            origins: vec![],
+        });
+        assert_eq!(
+            recipe.validate(),
+            Err(vec![
+                "population of es is not positive: 0".to_string(),
+                "'tea' has an origin in ja which is not a super language".to_string(),
+                "'tea' has a loan from es containing a schwa".to_string(),
+                "'dog' has no origins".to_string(),
+            ])
+        );
+        recipe.default_population = Some(1.0);
+        assert_eq!(
+            recipe.validate(),
+            Err(vec![
+                "population of es is not positive: 0".to_string(),
+                "'tea' has a loan from es containing a schwa".to_string(),
+                "'dog' has no origins".to_string(),
+            ])
+        );
+
+        let mut recipe = self::recipe();
+        recipe.super_words[0].origins[1].language = "sw".into();
+        recipe.super_words[1].origins[0].loan = None;
+        recipe.super_words[1].origins[0].ipa = Some("tʰə".into());
+        recipe.default_population = Some(1.0);
+        assert_eq!(
+            recipe.validate(),
+            Err(vec![
+                "'cat' from sw has no IPA and there is no IPA source for sw".to_string(),
+                "'tea' has a loan from en containing a schwa".to_string(),
+            ])
+        );
+
+        let mut recipe = self::recipe();
+        recipe.super_words[0].origins[0].recency = Some(-1.0);
+        recipe.super_words[0].origins[1].recency = Some(f64::NAN);
+        recipe.super_words[1].origins[0].recency = Some(0.0);
+        assert_eq!(
+            recipe.validate(),
+            Err(vec![
+                "recency of 'cat' from en must be positive: -1".to_string(),
+                "recency of 'cat' from es must be positive: NaN".to_string(),
+                "recency of 'tea' from en must be positive: 0".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_from_csv() {
+        let csv = "language,population\nen,818.9\nja,126.26075\n";
+        let super_languages = SuperLanguage::from_csv(csv.as_bytes()).unwrap();
+        assert_eq!(super_languages.len(), 2);
+        assert_eq!(super_languages[0].language, "en");
+        assert_eq!(super_languages[0].population, 818.9);
+        assert_eq!(super_languages[1].language, "ja");
+        assert_eq!(super_languages[1].population, 126.26075);
+        assert!(SuperLanguage::from_csv("language,population\nxx,1\n".as_bytes()).is_err());
+        assert!(SuperLanguage::from_csv("language,population\nen,many\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_override_populations() {
+        let recipe = recipe().override_populations(vec![
+            SuperLanguage {
+                language: "es".into(),
+                population: 5.0,
+            },
+            SuperLanguage {
+                language: "ja".into(),
+                population: 2.0,
+            },
+        ]);
+        assert_eq!(population(&recipe.super_languages, "en"), 3.0);
+        assert_eq!(population(&recipe.super_languages, "es"), 5.0);
+        assert_eq!(population(&recipe.super_languages, "ja"), 2.0);
+    }
+
+    #[test]
+    fn test_phonemes_from_language() {
+        let recipe = recipe();
+        let en = recipe.phonemes_from_language("en");
+        assert_eq!(en.len(), 4);
+        assert_eq!(en[&Phoneme::K], 0.75);
+        assert_eq!(en[&Phoneme::A], 0.75);
+        assert_eq!(en[&Phoneme::T], 1.75);
+        assert_eq!(en[&Phoneme::I], 1.0);
+        let es = recipe.phonemes_from_language("es");
+        assert_eq!(es[&Phoneme::G], 0.25);
+        assert!(!es.contains_key(&Phoneme::K));
+        assert!(recipe.phonemes_from_language("ja").is_empty());
+
+        let mut recipe = self::recipe();
+        recipe.super_words[0].origins[1].recency = Some(3.0);
+        let es = recipe.phonemes_from_language("es");
+        assert_eq!(es[&Phoneme::G], 0.5);
+    }
+
+    #[test]
+    fn test_origin_recency_serde() {
+        let origin: Origin = serde_json::from_str(
+            r#"{"language": "en", "word": "cat", "loan": "kat", "recency": 0.5}"#,
+        )
+        .unwrap();
+        assert_eq!(origin.recency, Some(0.5));
+        assert_eq!(
+            serde_json::to_string(&origin).unwrap(),
+            r#"{"language":"en","word":"cat","ipa":null,"loan":"kat","recency":0.5}"#
+        );
+        let origin: Origin =
+            serde_json::from_str(r#"{"language": "en", "word": "cat", "loan": "kat"}"#).unwrap();
+        assert_eq!(origin.recency, None);
+        assert_eq!(
+            serde_json::to_string(&origin).unwrap(),
+            r#"{"language":"en","word":"cat","ipa":null,"loan":"kat"}"#
+        );
+    }
+}
@@ -1,15 +1,22 @@
 use std::{
     cmp::{self, Ordering},
-    collections::BTreeSet,
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+    hash::{Hash, Hasher},
+    io::{Read, Write},
 };
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
+    convert,
     phoneme::{Phoneme, PhonemeExt},
     phonotactics::PhonotacticsExt,
-    recipe::{SuperLanguage, SuperWord},
+    profile::Profile,
+    recipe::{self, Origin, SuperLanguage, SuperWord},
 };
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CandidateWord {
     pub score: f64,
     pub word: Vec<Phoneme>,
@@ -30,6 +37,24 @@ impl PartialEq for CandidateWord {
 
 impl Eq for CandidateWord {}
 
+// Like `eq`, the score is left out so that equal words collapse.
+impl Hash for CandidateWord {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.word.hash(state);
+    }
+}
+
+impl fmt::Display for CandidateWord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {:.6}",
+            convert::phonemes_to_loan(&self.word),
+            self.score
+        )
+    }
+}
+
 impl PartialOrd for CandidateWord {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         match self.score.partial_cmp(&other.score) {
@@ -56,39 +81,97 @@ pub struct WordGenerator<'a> {
     pub words: Vec<CandidateWord>,
     pub limit: usize,
     pub weight_sum: f64,
+    pub disabled_languages: BTreeSet<String>,
+    pub preferred_phonemes: HashMap<Phoneme, f64>,
+    pub phoneme_frequency: HashMap<Phoneme, f64>,
+    pub allowed_consonants: Option<HashSet<Phoneme>>,
+    pub blocklist: HashSet<String>,
+    pub quiet: bool,
+    profile: Profile,
 }
 
-impl WordGenerator<'_> {
-    fn calc_weight_sum(&mut self) {
-        self.weight_sum = self
-            .super_languages
+impl<'a> WordGenerator<'a> {
+    pub fn new(
+        super_languages: &'a Vec<SuperLanguage>,
+        super_word: SuperWord,
+        limit: usize,
+    ) -> Self {
+        WordGenerator {
+            super_languages,
+            super_word,
+            words: Vec::new(),
+            limit,
+            weight_sum: 0.0,
+            disabled_languages: BTreeSet::new(),
+            preferred_phonemes: HashMap::new(),
+            phoneme_frequency: HashMap::new(),
+            allowed_consonants: None,
+            blocklist: HashSet::new(),
+            quiet: false,
+            profile: Profile::default(),
+        }
+    }
+
+    pub fn disable_language(&mut self, language: &str) {
+        self.disabled_languages.insert(language.to_string());
+    }
+
+    pub fn enable_language(&mut self, language: &str) {
+        self.disabled_languages.remove(language);
+    }
+
+    pub fn restrict_to(&mut self, allowed: HashSet<Phoneme>) {
+        self.allowed_consonants = Some(allowed);
+    }
+
+    fn origins(&self) -> impl Iterator<Item = &Origin> {
+        self.super_word
+            .origins
             .iter()
-            .filter(|super_language| {
-                self.super_word
-                    .origins
-                    .iter()
-                    .any(|origin| origin.language == super_language.language)
-            })
-            .map(|super_language| super_language.population)
+            .filter(move |origin| !self.disabled_languages.contains(&origin.language))
+    }
+
+    fn total_weight(&self) -> f64 {
+        self.origins()
+            .map(|origin| self.origin_weight(origin))
             .sum()
     }
 
-    fn get_super_language(&self, language: &str) -> &SuperLanguage {
-        self.super_languages
-            .iter()
-            .find(|super_language| super_language.language == language)
-            .unwrap()
+    fn calc_weight_sum(&mut self) {
+        self.weight_sum = self.total_weight();
     }
 
     pub fn get_population(&self, language: &str) -> f64 {
-        self.get_super_language(language).population
+        recipe::population(self.super_languages, language)
+    }
+
+    pub fn origin_weight(&self, origin: &Origin) -> f64 {
+        origin.weight(self.super_languages)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, CandidateWord> {
+        self.words.iter()
+    }
+
+    pub fn save_results(&self, w: impl Write) -> serde_json::Result<()> {
+        serde_json::to_writer(w, &self.words)
+    }
+
+    pub fn load_results(r: impl Read) -> serde_json::Result<Vec<CandidateWord>> {
+        serde_json::from_reader(r)
+    }
+
+    pub fn score_word(&self, word: &[Phoneme]) -> CandidateWord {
+        CandidateWord {
+            score: self.score(word, self.total_weight()),
+            word: word.to_vec(),
+        }
     }
 
     fn candidate_length(&self) -> i32 {
         let mut sum = 0.0;
-        for origin in &self.super_word.origins {
-            sum += origin.loan.as_ref().unwrap().len() as f64
-                * self.get_population(&origin.language)
+        for origin in self.origins() {
+            sum += origin.loan.as_ref().unwrap().len() as f64 * self.origin_weight(origin)
                 / self.weight_sum;
         }
         let sum = sum.ceil() as i32;
@@ -102,7 +185,7 @@ impl WordGenerator<'_> {
     fn cadidate_phonemes(&self, n: i32) -> BTreeSet<Phoneme> {
         let c_len = self.candidate_length();
         let mut set = BTreeSet::new();
-        for origin in &self.super_word.origins {
+        for origin in self.origins() {
             let loan = origin.loan.as_ref().unwrap();
             let len = loan.len() as i32;
             if len < c_len {
@@ -139,88 +222,159 @@ impl WordGenerator<'_> {
                 set.insert(loan[n as usize]);
             }
         }
+        set.retain(|c| self.is_allowed(c));
         set
     }
 
+    fn is_allowed(&self, phoneme: &Phoneme) -> bool {
+        match &self.allowed_consonants {
+            Some(allowed) => !phoneme.is_consonant() || allowed.contains(phoneme),
+            None => true,
+        }
+    }
+
     pub fn generate(&mut self) {
-        self.calc_weight_sum();
+        let mut profile = Profile::default();
+        let len = profile.time("initialize", || {
+            self.calc_weight_sum();
+            self.candidate_length()
+        });
         let vec = vec![CandidateWord {
             score: 0.0,
             word: vec![],
         }];
-        self.words = self.generate_rec(0, self.candidate_length(), vec);
+        self.words = self.generate_rec(0, len, vec, &mut profile);
+        self.profile = profile;
+    }
+
+    // Time spent in each phase of the last `generate` call.
+    pub fn profile(&self) -> String {
+        self.profile.to_string()
     }
 
-    fn generate_rec(&self, n: i32, len: i32, last_vec: Vec<CandidateWord>) -> Vec<CandidateWord> {
+    fn is_blocked(&self, word: &[Phoneme]) -> bool {
+        let loan = convert::phonemes_to_loan(word).to_lowercase();
+        self.blocklist
+            .iter()
+            .any(|blocked| blocked.to_lowercase() == loan)
+    }
+
+    fn generate_rec(
+        &self,
+        n: i32,
+        len: i32,
+        last_vec: Vec<CandidateWord>,
+        profile: &mut Profile,
+    ) -> Vec<CandidateWord> {
         if n >= len {
             last_vec
-        } else if self.super_word.origins.len() == 1 {
-            let mut vec = Vec::new();
-            for origin in &self.super_word.origins {
-                let loan = origin.loan.as_ref().unwrap();
-                let ncw = CandidateWord {
-                    score: self.score(&loan),
-                    word: loan.clone(),
-                };
-                vec.push(ncw);
-            }
-            vec
+        } else if self.origins().count() == 1 {
+            profile.time("enumerate", || self.loans())
         } else {
-            let mut vec = Vec::new();
-            let cps = self.cadidate_phonemes(n);
-            for cw in last_vec {
-                for cp in &cps {
-                    let mut ncww = cw.word.clone();
-                    ncww.push(*cp);
-                    if ncww.is_match_rfc_9_3()
-                        && ncww.is_match_rfc_9_5()
-                        && ncww.is_match_rfc_9_7()
-                        && ((n == 0 && ncww.is_match_rfc_9_6())
-                            || (n == len - 1 && ncww.is_match_rfc_9_4())
-                            || (n != 0 && n != len - 1))
-                    {
-                        let ncw = CandidateWord {
-                            score: self.score(&ncww),
-                            word: ncww,
-                        };
-                        vec.push(ncw);
-                    }
-                }
+            let cps = profile.time("candidates", || self.cadidate_phonemes(n));
+            let vec = profile.time("enumerate", || self.extend(n, len, last_vec, &cps));
+            if !self.quiet {
+                println!("n = {}, len = {}, set.len() = {}", n, len, vec.len());
+            }
+            self.generate_rec(n + 1, len, vec, profile)
+        }
+    }
+
+    fn loans(&self) -> Vec<CandidateWord> {
+        let mut vec = Vec::new();
+        for origin in self.origins() {
+            let loan = origin.loan.as_ref().unwrap();
+            if !loan.iter().all(|c| self.is_allowed(c)) || self.is_blocked(loan) {
+                continue;
             }
-            vec.sort_unstable();
-            vec.dedup();
-            if vec.len() > self.limit {
-                vec = vec.into_iter().take(self.limit).collect();
+            let ncw = CandidateWord {
+                score: self.score(loan, self.weight_sum),
+                word: loan.clone(),
+            };
+            vec.push(ncw);
+        }
+        vec
+    }
+
+    fn extend(
+        &self,
+        n: i32,
+        len: i32,
+        last_vec: Vec<CandidateWord>,
+        cps: &BTreeSet<Phoneme>,
+    ) -> Vec<CandidateWord> {
+        let mut vec = Vec::new();
+        for cw in last_vec {
+            for cp in cps {
+                let mut ncww = cw.word.clone();
+                ncww.push(*cp);
+                if ncww.is_match_rfc_9_3()
+                    && ncww.is_match_rfc_9_5()
+                    && ncww.is_match_rfc_9_7()
+                    && ((n == 0 && ncww.is_match_rfc_9_6())
+                        || (n == len - 1 && ncww.is_match_rfc_9_4())
+                        || (n != 0 && n != len - 1))
+                    && !(n == len - 1 && self.is_blocked(&ncww))
+                {
+                    let ncw = CandidateWord {
+                        score: self.score(&ncww, self.weight_sum),
+                        word: ncww,
+                    };
+                    vec.push(ncw);
+                }
             }
-            println!("n = {}, len = {}, set.len() = {}", n, len, vec.len());
-            self.generate_rec(n + 1, len, vec)
         }
+        vec.sort_unstable();
+        vec.dedup();
+        if vec.len() > self.limit {
+            vec = vec.into_iter().take(self.limit).collect();
+        }
+        vec
+    }
+
+    fn frequency_factor(&self, word: &[Phoneme]) -> f64 {
+        word.iter()
+            .map(|phoneme| self.phoneme_frequency.get(phoneme).unwrap_or(&1.0))
+            .sum::<f64>()
+            / word.len() as f64
     }
 
-    fn score(&self, word: &Vec<Phoneme>) -> f64 {
+    fn score(&self, word: &[Phoneme], weight_sum: f64) -> f64 {
         if word.len() < 2 {
             0.0
         } else {
             let mut score = 0.0;
-            for origin in &self.super_word.origins {
+            for origin in self.origins() {
                 let loanword = origin.loan.as_ref().unwrap();
-                let language = &origin.language;
                 'search: for i in (1..word.len()).rev() {
                     for j in 0..=word.len() - i {
                         let subword = &word[j..j + i];
                         if array_in_array(subword, &loanword) {
-                            score += i as f64 * self.get_population(&language) / self.weight_sum
+                            score += i as f64 * self.origin_weight(origin) / weight_sum
                                 * (if i == 1 { 0.001 } else { 1.0 });
                             break 'search;
                         }
                     }
                 }
             }
-            score
+            score * self.frequency_factor(word)
+                + word
+                    .iter()
+                    .filter_map(|phoneme| self.preferred_phonemes.get(phoneme))
+                    .sum::<f64>()
         }
     }
 }
 
+impl<'a> IntoIterator for &'a WordGenerator<'_> {
+    type Item = &'a CandidateWord;
+    type IntoIter = std::slice::Iter<'a, CandidateWord>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 pub fn array_in_array(needle: &[Phoneme], haystack: &[Phoneme]) -> bool {
     if needle.len() > haystack.len() {
         false
@@ -258,3 +412,274 @@ fn test_array_in_array() {
         true
     );
 }
+
+#[cfg(test)]
+fn test_super_languages() -> Vec<SuperLanguage> {
+    vec![
+        SuperLanguage {
+            language: "en".into(),
+            population: 2.0,
+        },
+        SuperLanguage {
+            language: "es".into(),
+            population: 1.0,
+        },
+    ]
+}
+
+#[cfg(test)]
+use crate::convert::loan_to_phonemes;
+
+#[cfg(test)]
+fn test_super_word() -> SuperWord {
+    SuperWord {
+        id: 0,
+        meaning: "cat".into(),
+        origins: vec![
+            Origin {
+                language: "en".into(),
+                word: "cat".into(),
+                ipa: None,
+                loan: Some(loan_to_phonemes("kat")),
+                recency: None,
+            },
+            Origin {
+                language: "es".into(),
+                word: "gato".into(),
+                ipa: None,
+                loan: Some(loan_to_phonemes("gato")),
+                recency: None,
+            },
+        ],
+    }
+}
+
+#[cfg(test)]
+fn test_word_generator(super_languages: &Vec<SuperLanguage>) -> WordGenerator<'_> {
+    WordGenerator::new(super_languages, test_super_word(), 1000)
+}
+
+#[test]
+fn test_into_iterator() {
+    let super_languages = test_super_languages();
+    let mut word_generator = test_word_generator(&super_languages);
+    word_generator.generate();
+    let mut words = Vec::new();
+    for candidate in &word_generator {
+        words.push(candidate.word.clone());
+    }
+    assert!(!words.is_empty());
+    assert_eq!(
+        words,
+        word_generator
+            .words
+            .iter()
+            .map(|c| c.word.clone())
+            .collect::<Vec<Vec<Phoneme>>>()
+    );
+}
+
+#[test]
+fn test_save_and_load_results() {
+    let super_languages = test_super_languages();
+    let mut word_generator = test_word_generator(&super_languages);
+    word_generator.generate();
+    let mut buffer = Vec::new();
+    word_generator.save_results(&mut buffer).unwrap();
+    let loaded = WordGenerator::load_results(buffer.as_slice()).unwrap();
+    assert_eq!(loaded.len(), word_generator.words.len());
+    for (a, b) in loaded.iter().zip(word_generator.iter()) {
+        assert_eq!(a.word, b.word);
+        assert_eq!(a.score.to_bits(), b.score.to_bits());
+    }
+}
+
+#[test]
+fn test_disable_language() {
+    let super_languages = test_super_languages();
+    let mut word_generator = test_word_generator(&super_languages);
+    word_generator.disable_language("es");
+    word_generator.generate();
+    assert_eq!(word_generator.weight_sum, 2.0);
+    let regular_weight_sum: f64 = word_generator
+        .origins()
+        .map(|origin| word_generator.get_population(&origin.language) / word_generator.weight_sum)
+        .sum();
+    assert_eq!(regular_weight_sum, 1.0);
+    assert_eq!(word_generator.words[0].word, loan_to_phonemes("kat"));
+    word_generator.enable_language("es");
+    word_generator.generate();
+    assert_eq!(word_generator.weight_sum, 3.0);
+}
+
+#[test]
+fn test_preferred_phonemes() {
+    let super_languages = test_super_languages();
+    let mut word_generator = test_word_generator(&super_languages);
+    word_generator.super_word.origins[1].loan = Some(loan_to_phonemes("lat"));
+    word_generator.generate();
+    assert_eq!(word_generator.words[0].word, loan_to_phonemes("kat"));
+    word_generator.preferred_phonemes.insert(Phoneme::L, 0.1);
+    word_generator.generate();
+    assert_eq!(word_generator.words[0].word, loan_to_phonemes("lat"));
+}
+
+#[test]
+fn test_phoneme_frequency() {
+    let super_languages = test_super_languages();
+    let mut word_generator = test_word_generator(&super_languages);
+    word_generator.super_word.origins[1].loan = Some(loan_to_phonemes("lat"));
+    word_generator.generate();
+    assert_eq!(word_generator.words[0].word, loan_to_phonemes("kat"));
+    word_generator.phoneme_frequency.insert(Phoneme::L, 3.0);
+    word_generator.generate();
+    assert_eq!(word_generator.words[0].word, loan_to_phonemes("lat"));
+}
+
+#[test]
+fn test_hash_ignores_score() {
+    use std::collections::HashSet;
+
+    let mut set = HashSet::new();
+    set.insert(CandidateWord {
+        score: 1.0,
+        word: loan_to_phonemes("kat"),
+    });
+    set.insert(CandidateWord {
+        score: 2.0,
+        word: loan_to_phonemes("kat"),
+    });
+    set.insert(CandidateWord {
+        score: 1.0,
+        word: loan_to_phonemes("gat"),
+    });
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn test_recency() {
+    let super_languages = test_super_languages();
+    let mut word_generator = test_word_generator(&super_languages);
+    word_generator.super_word.origins[1].loan = Some(loan_to_phonemes("lit"));
+    word_generator.generate();
+    assert_eq!(word_generator.words[0].word, loan_to_phonemes("kat"));
+    word_generator.super_word.origins[1].recency = Some(3.0);
+    word_generator.generate();
+    assert_eq!(word_generator.words[0].word, loan_to_phonemes("kit"));
+    assert_eq!(word_generator.weight_sum, 5.0);
+    let regular_weight_sum: f64 = word_generator
+        .origins()
+        .map(|origin| word_generator.origin_weight(origin) / word_generator.weight_sum)
+        .sum();
+    assert_eq!(regular_weight_sum, 1.0);
+}
+
+#[test]
+fn test_score_word() {
+    let super_languages = test_super_languages();
+    let word_generator = test_word_generator(&super_languages);
+    let a = word_generator.score_word(&loan_to_phonemes("katu"));
+    let other_super_languages = vec![
+        SuperLanguage {
+            language: "en".into(),
+            population: 1.0,
+        },
+        SuperLanguage {
+            language: "es".into(),
+            population: 2.0,
+        },
+    ];
+    let other_word_generator = test_word_generator(&other_super_languages);
+    let b = other_word_generator.score_word(&loan_to_phonemes("katu"));
+    assert_eq!(a.word, b.word);
+    assert!(a.score > b.score);
+}
+
+#[test]
+fn test_restrict_to() {
+    use crate::convert::inventory_to_phonemes;
+
+    let super_languages = test_super_languages();
+    let mut word_generator = test_word_generator(&super_languages);
+    word_generator.super_word.origins[1].loan = Some(loan_to_phonemes("lat"));
+    word_generator.restrict_to(inventory_to_phonemes("l, t").unwrap());
+    word_generator.generate();
+    assert_eq!(word_generator.words.len(), 1);
+    assert_eq!(word_generator.words[0].word, loan_to_phonemes("lat"));
+}
+
+#[test]
+fn test_restrict_to_single_origin() {
+    use crate::convert::inventory_to_phonemes;
+
+    let super_languages = test_super_languages();
+    let mut word_generator = test_word_generator(&super_languages);
+    word_generator.disable_language("es");
+    word_generator.restrict_to(inventory_to_phonemes("l, t").unwrap());
+    word_generator.generate();
+    assert!(word_generator.words.is_empty());
+
+    word_generator.restrict_to(inventory_to_phonemes("k, t").unwrap());
+    word_generator.generate();
+    assert_eq!(word_generator.words.len(), 1);
+    assert_eq!(word_generator.words[0].word, loan_to_phonemes("kat"));
+
+    let mut word_generator = test_word_generator(&super_languages);
+    word_generator.super_word.origins.truncate(1);
+    word_generator.restrict_to(inventory_to_phonemes("l, t").unwrap());
+    word_generator.generate();
+    assert!(word_generator.words.is_empty());
+}
+
+#[test]
+fn test_profile() {
+    let super_languages = test_super_languages();
+    let mut word_generator = test_word_generator(&super_languages);
+    word_generator.generate();
+    let profile = word_generator.profile();
+    for phase in &["initialize", "candidates", "enumerate"] {
+        assert_eq!(profile.matches(&format!("|{}|", phase)).count(), 1);
+    }
+
+    word_generator.disable_language("es");
+    word_generator.generate();
+    let profile = word_generator.profile();
+    assert!(profile.contains("|enumerate|"));
+    assert!(!profile.contains("|candidates|"));
+}
+
+#[test]
+fn test_blocklist() {
+    let super_languages = test_super_languages();
+    let mut word_generator = test_word_generator(&super_languages);
+    word_generator.super_word.origins[1].loan = Some(loan_to_phonemes("lat"));
+    word_generator.blocklist.insert("KAT".into());
+    word_generator.generate();
+    assert_eq!(word_generator.words[0].word, loan_to_phonemes("lat"));
+    assert!(word_generator
+        .iter()
+        .all(|c| c.word != loan_to_phonemes("kat")));
+    word_generator.super_word.origins[1].loan = Some(loan_to_phonemes("lad"));
+    word_generator.limit = 2;
+    word_generator.generate();
+    assert_eq!(word_generator.words.len(), 2);
+    assert!(word_generator
+        .iter()
+        .all(|c| c.word != loan_to_phonemes("kat")));
+    word_generator.disable_language("es");
+    word_generator.generate();
+    assert!(word_generator.words.is_empty());
+}
+
+#[test]
+fn test_golden_candidates() {
+    let super_languages = test_super_languages();
+    let mut word_generator = test_word_generator(&super_languages);
+    word_generator.generate();
+    let output: String = word_generator
+        .iter()
+        .take(10)
+        .map(|candidate| format!("{}\n", candidate))
+        .collect();
+    assert_eq!(output, include_str!("../data/golden/candidates.txt"));
+}